@@ -0,0 +1,370 @@
+//! Machine-applicable autofix engine.
+//!
+//! Turns `MachineApplicable` rustc suggestions into byte-range edits, applies
+//! the non-overlapping ones, and leaves everything else (`MaybeIncorrect`,
+//! `HasPlaceholders`, `Unspecified`) as advisory-only so a fix never corrupts
+//! source the compiler wasn't fully confident about.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::rustc_diagnostics::{self, Applicability, RustcDiagnostic, RustcSpan};
+use crate::scanner;
+
+/// A single byte-range replacement extracted from a rustc suggestion span.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edit {
+    pub file: PathBuf,
+    pub start_byte: u32,
+    pub end_byte: u32,
+    pub new_text: String,
+}
+
+/// The result of running the autofix engine over a file.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutofixReport {
+    /// The file's contents after every non-overlapping machine-applicable
+    /// edit has been applied.
+    pub patched_source: String,
+    /// A unified diff between the original and patched source.
+    pub unified_diff: String,
+    /// Edits that were applied.
+    pub applied: Vec<Edit>,
+    /// Machine-applicable edits dropped because they overlapped an edit that
+    /// was kept and starts at the same offset or earlier in the file.
+    pub skipped_overlaps: Vec<Edit>,
+    /// Suggestions rustc was not fully confident in. Never applied
+    /// automatically, but returned so an agent can review them.
+    pub advisory: Vec<Edit>,
+}
+
+/// Builds an edit from a suggestion span, translating its chunk-relative
+/// byte offsets back to offsets in the whole original file by adding
+/// `chunk_offset` (the chunk's own start offset in that file).
+fn edit_from_span(file: &Path, span: &RustcSpan, chunk_offset: u32) -> Option<(Applicability, Edit)> {
+    let new_text = span.suggested_replacement.clone()?;
+    let applicability = span.suggestion_applicability?;
+    Some((
+        applicability,
+        Edit {
+            file: file.to_path_buf(),
+            start_byte: chunk_offset + span.byte_start,
+            end_byte: chunk_offset + span.byte_end,
+            new_text,
+        },
+    ))
+}
+
+/// Walks a diagnostic and its children (rustc attaches suggestions to both)
+/// and splits their spans into machine-applicable and advisory edits.
+fn collect_edits(
+    file: &Path,
+    diagnostic: &RustcDiagnostic,
+    chunk_offset: u32,
+    machine: &mut Vec<Edit>,
+    advisory: &mut Vec<Edit>,
+) {
+    for span in &diagnostic.spans {
+        if let Some((applicability, edit)) = edit_from_span(file, span, chunk_offset) {
+            match applicability {
+                Applicability::MachineApplicable => machine.push(edit),
+                _ => advisory.push(edit),
+            }
+        }
+    }
+    for child in &diagnostic.children {
+        collect_edits(file, child, chunk_offset, machine, advisory);
+    }
+}
+
+/// Sorts edits by start offset and drops any edit that overlaps one already
+/// kept, so every remaining edit can be applied independently of the rest.
+fn drop_overlaps(mut edits: Vec<Edit>) -> (Vec<Edit>, Vec<Edit>) {
+    edits.sort_by_key(|edit| edit.start_byte);
+    let mut kept: Vec<Edit> = Vec::with_capacity(edits.len());
+    let mut dropped = Vec::new();
+    for edit in edits {
+        let overlaps = kept
+            .last()
+            .is_some_and(|last: &Edit| edit.start_byte < last.end_byte);
+        if overlaps {
+            dropped.push(edit);
+        } else {
+            kept.push(edit);
+        }
+    }
+    (kept, dropped)
+}
+
+/// Applies non-overlapping edits to `source`, walking back to front so
+/// earlier byte offsets stay valid as later ones are rewritten.
+pub fn apply_edits(source: &str, edits: &[Edit]) -> String {
+    let mut patched = source.to_string();
+    for edit in edits.iter().rev() {
+        let start = edit.start_byte as usize;
+        let end = edit.end_byte as usize;
+        patched.replace_range(start..end, &edit.new_text);
+    }
+    patched
+}
+
+/// Runs the compiler over `path`, applies every safe suggestion, and returns
+/// a report covering what changed and what was left for a human to review.
+///
+/// Like [`crate::diagnostics::detect_file`], this prefers compiling the
+/// whole file in one shot, since suggestions that rely on cross-item
+/// context (one function calling another) only come out right that way. It
+/// only falls back to compiling one [`scanner::split_top_level_chunks`]
+/// chunk at a time when the whole file hits a fatal parse error somewhere,
+/// so that doesn't stop rustc from suggesting fixes for every other,
+/// independently broken item.
+pub fn autofix_file(path: &Path) -> io::Result<AutofixReport> {
+    let source = fs::read_to_string(path)?;
+
+    let mut machine = Vec::new();
+    let mut advisory = Vec::new();
+    let whole_file = rustc_diagnostics::check_source(&source)?;
+    if rustc_diagnostics::parsed_completely(&whole_file) {
+        for diagnostic in &whole_file {
+            collect_edits(path, diagnostic, 0, &mut machine, &mut advisory);
+        }
+    } else {
+        for (start, end) in scanner::split_top_level_chunks(&source) {
+            let diagnostics = rustc_diagnostics::check_source(&source[start..end])?;
+            for diagnostic in &diagnostics {
+                collect_edits(path, diagnostic, start as u32, &mut machine, &mut advisory);
+            }
+        }
+    }
+
+    let (applied, skipped_overlaps) = drop_overlaps(machine);
+    let patched_source = apply_edits(&source, &applied);
+    let unified_diff = diff::render(path, &source, &patched_source);
+
+    Ok(AutofixReport {
+        patched_source,
+        unified_diff,
+        applied,
+        skipped_overlaps,
+        advisory,
+    })
+}
+
+/// A real unified-diff renderer: a line-level LCS diff grouped into `@@`
+/// hunks with standard context, just enough for `apply_fixes` to hand back
+/// something a human (or `git apply`/`patch`) can actually consume, without
+/// pulling in a full diff crate.
+mod diff {
+    use std::path::Path;
+
+    const CONTEXT: usize = 3;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Tag {
+        Equal,
+        Delete,
+        Insert,
+    }
+
+    /// One line of the edit script: which side(s) of the diff it came from,
+    /// and its 0-based index on that side.
+    #[derive(Debug, Clone, Copy)]
+    struct Op {
+        tag: Tag,
+        before_index: usize,
+        after_index: usize,
+    }
+
+    /// Lengths of the longest common subsequence of `a[i..]` and `b[j..]`,
+    /// `table[i][j]`, computed bottom-up so the edit script can be read off
+    /// by walking the table forward from `(0, 0)`.
+    fn lcs_lengths(a: &[&str], b: &[&str]) -> Vec<Vec<u32>> {
+        let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                table[i][j] = if a[i] == b[j] {
+                    table[i + 1][j + 1] + 1
+                } else {
+                    table[i + 1][j].max(table[i][j + 1])
+                };
+            }
+        }
+        table
+    }
+
+    /// Walks the LCS table into a minimal sequence of equal/delete/insert
+    /// ops that turns `a` into `b`.
+    fn edit_script(a: &[&str], b: &[&str]) -> Vec<Op> {
+        let table = lcs_lengths(a, b);
+        let mut ops = Vec::new();
+        let (mut i, mut j) = (0usize, 0usize);
+        while i < a.len() && j < b.len() {
+            if a[i] == b[j] {
+                ops.push(Op {
+                    tag: Tag::Equal,
+                    before_index: i,
+                    after_index: j,
+                });
+                i += 1;
+                j += 1;
+            } else if table[i + 1][j] >= table[i][j + 1] {
+                ops.push(Op {
+                    tag: Tag::Delete,
+                    before_index: i,
+                    after_index: j,
+                });
+                i += 1;
+            } else {
+                ops.push(Op {
+                    tag: Tag::Insert,
+                    before_index: i,
+                    after_index: j,
+                });
+                j += 1;
+            }
+        }
+        while i < a.len() {
+            ops.push(Op {
+                tag: Tag::Delete,
+                before_index: i,
+                after_index: j,
+            });
+            i += 1;
+        }
+        while j < b.len() {
+            ops.push(Op {
+                tag: Tag::Insert,
+                before_index: i,
+                after_index: j,
+            });
+            j += 1;
+        }
+        ops
+    }
+
+    /// Groups an edit script into hunks, padding each change with up to
+    /// [`CONTEXT`] lines of surrounding equal lines and merging hunks whose
+    /// context would otherwise overlap, the same shape `diff -u` produces.
+    fn group_hunks(ops: &[Op]) -> Vec<&[Op]> {
+        let mut hunks = Vec::new();
+        let mut hunk_start: Option<usize> = None;
+        let mut last_change: usize = 0;
+
+        for (index, op) in ops.iter().enumerate() {
+            if op.tag == Tag::Equal {
+                continue;
+            }
+            match hunk_start {
+                None => {
+                    hunk_start = Some(index.saturating_sub(CONTEXT));
+                }
+                Some(start) => {
+                    // Equal run between the previous change and this one.
+                    if index - last_change > CONTEXT * 2 {
+                        hunks.push((start, last_change + CONTEXT));
+                        hunk_start = Some(index.saturating_sub(CONTEXT));
+                    }
+                }
+            }
+            last_change = index;
+        }
+        if let Some(start) = hunk_start {
+            hunks.push((start, (last_change + CONTEXT + 1).min(ops.len())));
+        }
+
+        hunks
+            .into_iter()
+            .map(|(start, end)| &ops[start..end.min(ops.len())])
+            .collect()
+    }
+
+    fn render_hunk(before: &[&str], after: &[&str], hunk: &[Op]) -> String {
+        let before_start = hunk[0].before_index;
+        let after_start = hunk[0].after_index;
+        let before_len = hunk.iter().filter(|op| op.tag != Tag::Insert).count();
+        let after_len = hunk.iter().filter(|op| op.tag != Tag::Delete).count();
+
+        let mut out = format!(
+            "@@ -{},{} +{},{} @@\n",
+            before_start + 1,
+            before_len,
+            after_start + 1,
+            after_len
+        );
+        for op in hunk {
+            match op.tag {
+                Tag::Equal => {
+                    out.push(' ');
+                    out.push_str(before[op.before_index]);
+                    out.push('\n');
+                }
+                Tag::Delete => {
+                    out.push('-');
+                    out.push_str(before[op.before_index]);
+                    out.push('\n');
+                }
+                Tag::Insert => {
+                    out.push('+');
+                    out.push_str(after[op.after_index]);
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders a standard unified diff (`@@ -l,s +l,s @@` hunks with
+    /// [`CONTEXT`] lines of surrounding context) between `before` and
+    /// `after`, suitable for `git apply`/`patch`.
+    pub fn render(path: &Path, before: &str, after: &str) -> String {
+        if before == after {
+            return String::new();
+        }
+        let before_lines: Vec<&str> = before.lines().collect();
+        let after_lines: Vec<&str> = after.lines().collect();
+        let ops = edit_script(&before_lines, &after_lines);
+
+        let file = path.display();
+        let mut out = format!("--- a/{file}\n+++ b/{file}\n");
+        for hunk in group_hunks(&ops) {
+            out.push_str(&render_hunk(&before_lines, &after_lines, hunk));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-rust-errors.rs")
+    }
+
+    /// Regression test for the bug where running `apply_fixes` against the
+    /// project's own multi-case fixture produced zero edits: a single fatal
+    /// parse error anywhere in the file (cases 9-12) used to stop rustc from
+    /// reporting suggestions for every independent item before it.
+    #[test]
+    fn autofix_file_one_shots_cases_one_five_and_seven() {
+        let report = autofix_file(&fixture_path()).expect("autofix_file should succeed");
+
+        assert!(
+            !report.applied.is_empty(),
+            "expected at least one machine-applicable fix against the real fixture"
+        );
+
+        let patched = &report.patched_source;
+        // Case 1: missing semicolon after `let x = 5`.
+        assert!(patched.contains("let x = 5;\n"));
+        // Case 5: missing comma after `field2: i32`.
+        assert!(patched.contains("field2: i32,\n"));
+        // Case 7: missing comma after the `2 => println!("two")` match arm.
+        assert!(patched.contains("println!(\"two\"),\n"));
+
+        assert!(report.unified_diff.contains("@@"));
+    }
+}
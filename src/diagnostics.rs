@@ -0,0 +1,445 @@
+//! The unified diagnostic model every detection pass feeds into.
+//!
+//! rustc diagnostics and the recovery [`scanner`](crate::scanner)'s findings
+//! are normalized into a single [`Diagnostic`] so a consumer walks one
+//! phase-sorted list instead of juggling each pass's own format. Beyond the
+//! primary span, a diagnostic also carries [`Label`]s for the secondary
+//! spans that explain it (the move site behind a use-after-move, the
+//! binding a "consider adding `mut`" help refers to, ...), so a client can
+//! render a multi-span annotated snippet rather than a single arrow.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::explain::{self, EnrichedDiagnostic};
+use crate::rustc_diagnostics::{self, RustcDiagnostic, RustcSpan};
+use crate::scanner::{self, ScanDiagnostic};
+
+/// Which stage of the compiler pipeline a diagnostic belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    LexParse,
+    NameResolution,
+    TypeCheck,
+    BorrowCheck,
+}
+
+/// What kind of problem a diagnostic describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    Syntax,
+    Borrow,
+    Type,
+    Lifetime,
+    Resolution,
+    Mutability,
+}
+
+impl DiagnosticKind {
+    /// Which pipeline phase this kind of problem is detected in.
+    pub fn phase(self) -> Phase {
+        match self {
+            DiagnosticKind::Syntax => Phase::LexParse,
+            DiagnosticKind::Resolution => Phase::NameResolution,
+            DiagnosticKind::Type => Phase::TypeCheck,
+            DiagnosticKind::Borrow | DiagnosticKind::Lifetime | DiagnosticKind::Mutability => {
+                Phase::BorrowCheck
+            }
+        }
+    }
+
+    /// Maps a rustc error code to the kind of problem it represents. Codeless
+    /// diagnostics are rustc's fatal parse errors (a typed error always
+    /// carries an E-code), so they fall back to `Syntax`; a coded diagnostic
+    /// we haven't classified yet falls back to `Type`, the most common kind.
+    fn from_rustc_code(code: Option<&str>) -> Self {
+        match code {
+            None => DiagnosticKind::Syntax,
+            Some("E0382") => DiagnosticKind::Borrow,
+            Some("E0308") => DiagnosticKind::Type,
+            Some("E0425") => DiagnosticKind::Resolution,
+            Some("E0384") | Some("E0594") => DiagnosticKind::Mutability,
+            Some("E0106") | Some("E0515") => DiagnosticKind::Lifetime,
+            _ => DiagnosticKind::Type,
+        }
+    }
+}
+
+/// How serious a diagnostic is, mirroring rustc's own levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    fn from_rustc_level(level: &str) -> Self {
+        match level {
+            "error" => Severity::Error,
+            "warning" => Severity::Warning,
+            _ => Severity::Note,
+        }
+    }
+}
+
+/// A source location, expressed the same way regardless of which pass
+/// produced the diagnostic.
+#[derive(Debug, Clone, Serialize)]
+pub struct Location {
+    pub byte_offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    fn from_span(span: &RustcSpan) -> Self {
+        Location {
+            byte_offset: span.byte_start as usize,
+            line: span.line_start as usize,
+            column: span.column_start as usize,
+        }
+    }
+}
+
+/// Whether a [`Label`] points at the spot the error actually is, or at
+/// supporting context elsewhere in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelStyle {
+    Primary,
+    Secondary,
+}
+
+/// A secondary annotation explaining a diagnostic, e.g. the move site
+/// behind a use-after-move, or the binding a "consider adding `mut`" help
+/// points at. Rendered alongside the diagnostic's own location so a client
+/// can draw a multi-span annotated snippet instead of just one arrow.
+#[derive(Debug, Clone, Serialize)]
+pub struct Label {
+    pub location: Location,
+    pub message: String,
+    pub style: LabelStyle,
+}
+
+/// A single normalized diagnostic, optionally chained to the diagnostic
+/// that caused it (e.g. the move that a later use-after-move complains
+/// about).
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub phase: Phase,
+    pub severity: Severity,
+    pub message: String,
+    pub code: Option<String>,
+    pub location: Location,
+    pub explanation: Option<&'static str>,
+    pub caused_by: Option<Box<Diagnostic>>,
+    pub labels: Vec<Label>,
+    pub suggestion: Option<String>,
+}
+
+/// If a diagnostic's own secondary spans point at an earlier piece of code
+/// that caused it (today: the move site behind an E0382 use-after-move,
+/// labeled "value moved here" — or "value moved into closure here" when the
+/// move happens via a closure capture — on the diagnostic itself rather
+/// than on a child), builds the cause as its own `Diagnostic` so callers can
+/// walk the chain.
+fn caused_by(diagnostic: &RustcDiagnostic) -> Option<Box<Diagnostic>> {
+    let code = diagnostic.code.as_ref()?.code.as_str();
+    if code != "E0382" {
+        return None;
+    }
+    let cause_span = diagnostic.spans.iter().find(|span| {
+        span.label.as_deref().is_some_and(|label| {
+            let label = label.to_lowercase();
+            label.contains("moved here") || label.contains("moved into closure here")
+        })
+    })?;
+    Some(Box::new(Diagnostic {
+        kind: DiagnosticKind::Borrow,
+        phase: Phase::BorrowCheck,
+        severity: Severity::Note,
+        message: cause_span
+            .label
+            .clone()
+            .expect("find() only matches spans with a label"),
+        code: None,
+        location: Location::from_span(cause_span),
+        explanation: None,
+        caused_by: None,
+        labels: Vec::new(),
+        suggestion: None,
+    }))
+}
+
+/// Collects every labeled span on a diagnostic and its children into
+/// [`Label`]s: the diagnostic's own spans keep their primary/secondary
+/// split, while every span a child attaches (notes, helps, ...) is
+/// secondary context supporting the primary message.
+fn labels_for(diagnostic: &RustcDiagnostic) -> Vec<Label> {
+    let mut labels = Vec::new();
+
+    for span in &diagnostic.spans {
+        if let Some(message) = &span.label {
+            labels.push(Label {
+                location: Location::from_span(span),
+                message: message.clone(),
+                style: if span.is_primary {
+                    LabelStyle::Primary
+                } else {
+                    LabelStyle::Secondary
+                },
+            });
+        }
+    }
+
+    for child in &diagnostic.children {
+        for span in &child.spans {
+            let message = span.label.clone().unwrap_or_else(|| child.message.clone());
+            labels.push(Label {
+                location: Location::from_span(span),
+                message,
+                style: LabelStyle::Secondary,
+            });
+        }
+    }
+
+    labels
+}
+
+/// Renders the first suggested replacement a diagnostic's children carry as
+/// a human-readable suggestion, e.g. "consider making this binding mutable:
+/// `mut x`".
+fn suggestion_for(diagnostic: &RustcDiagnostic) -> Option<String> {
+    diagnostic.children.iter().find_map(|child| {
+        child.spans.iter().find_map(|span| {
+            span.suggested_replacement
+                .as_ref()
+                .map(|replacement| format!("{}: `{replacement}`", child.message))
+        })
+    })
+}
+
+fn from_rustc(enriched: EnrichedDiagnostic) -> Option<Diagnostic> {
+    let EnrichedDiagnostic {
+        diagnostic,
+        explanation,
+    } = enriched;
+    let span = rustc_diagnostics::primary_span(&diagnostic)?;
+    let code = diagnostic.code.as_ref().map(|c| c.code.clone());
+    let kind = DiagnosticKind::from_rustc_code(code.as_deref());
+    Some(Diagnostic {
+        kind,
+        phase: kind.phase(),
+        severity: Severity::from_rustc_level(&diagnostic.level),
+        message: diagnostic.message.clone(),
+        code,
+        location: Location::from_span(span),
+        explanation,
+        caused_by: caused_by(&diagnostic),
+        labels: labels_for(&diagnostic),
+        suggestion: suggestion_for(&diagnostic),
+    })
+}
+
+fn from_scan(scan: ScanDiagnostic) -> Diagnostic {
+    Diagnostic {
+        kind: DiagnosticKind::Syntax,
+        phase: Phase::LexParse,
+        severity: Severity::Error,
+        message: scan.message,
+        code: None,
+        location: Location {
+            byte_offset: scan.byte_offset,
+            line: scan.line,
+            column: scan.column,
+        },
+        explanation: None,
+        caused_by: None,
+        labels: Vec::new(),
+        suggestion: scan.suggestion,
+    }
+}
+
+/// How far a chunk's own byte offsets and (1-based) line numbers sit from
+/// the start of the original file it was sliced out of, so a diagnostic
+/// rustc reports against the chunk in isolation can be translated back into
+/// coordinates that make sense against the whole file.
+struct ChunkOffset {
+    byte_offset: usize,
+    line_offset: usize,
+}
+
+fn shift_location(location: &mut Location, chunk: &ChunkOffset) {
+    location.byte_offset += chunk.byte_offset;
+    location.line += chunk.line_offset;
+}
+
+/// Translates a diagnostic's own location and every label/cause it carries
+/// from chunk-relative coordinates back to whole-file coordinates.
+fn shift_diagnostic(diagnostic: &mut Diagnostic, chunk: &ChunkOffset) {
+    shift_location(&mut diagnostic.location, chunk);
+    for label in &mut diagnostic.labels {
+        shift_location(&mut label.location, chunk);
+    }
+    if let Some(cause) = &mut diagnostic.caused_by {
+        shift_diagnostic(cause, chunk);
+    }
+}
+
+/// Every diagnostic found in a file, aggregated across the rustc-backed
+/// passes and the recovery scanner, sorted phase-then-position.
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectionReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Runs every detection pass over `path` and aggregates the results into a
+/// single phase-sorted report.
+///
+/// The rustc-backed pass prefers compiling the whole file in one shot, since
+/// that's the only way name resolution and type checking see cross-item
+/// references (one function calling another) the way they normally would.
+/// It only falls back to compiling one [`scanner::split_top_level_chunks`]
+/// chunk at a time when the whole file hits a fatal parse error somewhere
+/// (cases 9-12 in this project's own fixture) that would otherwise stop
+/// rustc from analyzing every other, unrelated item in the file.
+pub fn detect_file(path: &Path) -> io::Result<DetectionReport> {
+    let source = fs::read_to_string(path)?;
+
+    let whole_file = rustc_diagnostics::check_source(&source)?;
+    let mut diagnostics: Vec<Diagnostic> = if rustc_diagnostics::parsed_completely(&whole_file) {
+        explain::enrich(whole_file)
+            .into_iter()
+            .filter_map(from_rustc)
+            .collect()
+    } else {
+        let mut diagnostics = Vec::new();
+        for (start, end) in scanner::split_top_level_chunks(&source) {
+            let chunk = ChunkOffset {
+                byte_offset: start,
+                line_offset: source[..start].matches('\n').count(),
+            };
+            let chunk_diagnostics = rustc_diagnostics::check_source(&source[start..end])?;
+            for enriched in explain::enrich(chunk_diagnostics) {
+                if let Some(mut diagnostic) = from_rustc(enriched) {
+                    shift_diagnostic(&mut diagnostic, &chunk);
+                    diagnostics.push(diagnostic);
+                }
+            }
+        }
+        diagnostics
+    };
+
+    diagnostics.extend(scanner::scan(&source).into_iter().map(from_scan));
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.phase, diagnostic.location.byte_offset));
+
+    Ok(DetectionReport { diagnostics })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-rust-errors.rs")
+    }
+
+    /// Regression test for the bug where running `detect_file` against the
+    /// project's own multi-case fixture only ever surfaced `Syntax`
+    /// diagnostics: rustc's fatal parse error on cases 9-12's unclosed
+    /// delimiters used to block semantic analysis of the whole file, so the
+    /// borrow/type/resolution/mutability/lifetime cases earlier in the file
+    /// never got checked at all.
+    #[test]
+    fn detect_file_finds_every_kind_of_problem_in_the_real_fixture() {
+        let report = detect_file(&fixture_path()).expect("detect_file should succeed");
+
+        let has_kind = |kind: DiagnosticKind| report.diagnostics.iter().any(|d| d.kind == kind);
+        assert!(has_kind(DiagnosticKind::Syntax), "expected a syntax diagnostic");
+        assert!(
+            has_kind(DiagnosticKind::Borrow),
+            "expected a borrow diagnostic (case 2, use after move)"
+        );
+        assert!(
+            has_kind(DiagnosticKind::Type),
+            "expected a type diagnostic (case 3, type mismatch)"
+        );
+        assert!(
+            has_kind(DiagnosticKind::Resolution),
+            "expected a resolution diagnostic (case 4, undefined variable)"
+        );
+        assert!(
+            has_kind(DiagnosticKind::Lifetime),
+            "expected a lifetime diagnostic (case 6, missing lifetime specifier)"
+        );
+        assert!(
+            has_kind(DiagnosticKind::Mutability),
+            "expected a mutability diagnostic (case 8, immutable assignment)"
+        );
+
+        let use_after_move = report
+            .diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.code.as_deref() == Some("E0382"))
+            .expect("case 2's use-after-move should be detected");
+        assert!(
+            use_after_move.caused_by.is_some(),
+            "E0382 should chain to the move site that caused it"
+        );
+
+        assert!(
+            report
+                .diagnostics
+                .windows(2)
+                .all(|pair| pair[0].phase <= pair[1].phase),
+            "diagnostics should be sorted phase-then-position"
+        );
+    }
+
+    /// Regression test for the bug where `detect_file` always compiled one
+    /// [`scanner::split_top_level_chunks`] chunk at a time: a file whose
+    /// functions call each other (the ordinary case) got split across
+    /// chunks, so rustc couldn't see `register`'s definition while checking
+    /// `main`'s call to it, and reported a phantom `Resolution` diagnostic
+    /// ("cannot find function `register` in this scope") against code that
+    /// actually compiles cleanly.
+    ///
+    /// Note this doesn't assert zero diagnostics: compiling as a library (so
+    /// standalone snippets don't need a real `fn main`) means rustc's
+    /// dead-code lint never sees `main` as a true entry point, so it still,
+    /// correctly, warns that `main` and `register` are unused. That's a
+    /// separate, genuine property of `--crate-type=lib` checking, not the
+    /// cross-chunk resolution bug this test guards against.
+    #[test]
+    fn detect_file_does_not_invent_resolution_errors_for_cross_function_calls() {
+        let source = "fn register<T: Fn(i32) -> i32>(f: T) -> i32 { f(1) }\n\
+                       fn main() { println!(\"{}\", register(|x| x + 1)); }\n";
+        let path = std::env::temp_dir().join(format!(
+            "error-debugging-mcp-detect-file-cross-ref-test-{}.rs",
+            std::process::id()
+        ));
+        fs::write(&path, source).expect("should write temp fixture");
+
+        let report = detect_file(&path).expect("detect_file should succeed");
+        let _ = fs::remove_file(&path);
+
+        assert!(
+            !report
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.kind == DiagnosticKind::Resolution),
+            "expected no resolution diagnostic for a function defined and called \
+             in the same file, got: {:?}",
+            report.diagnostics
+        );
+    }
+}
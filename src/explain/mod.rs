@@ -0,0 +1,67 @@
+//! Error-code explanation catalog.
+//!
+//! Ships a long-form explanation per error code, laid out like
+//! `rustc --explain <code>` output (an erroneous example, why it fails, and
+//! the fix). The prose is this crate's own, not the text rustc prints. It is
+//! embedded at compile time via [`include_str!`] so the catalog stays plain
+//! markdown files that are easy to extend as we cover more of the E-code range.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::rustc_diagnostics::RustcDiagnostic;
+
+macro_rules! catalog {
+    ($($code:literal => $path:literal),+ $(,)?) => {
+        fn build_catalog() -> HashMap<&'static str, &'static str> {
+            HashMap::from([
+                $(($code, include_str!($path))),+
+            ])
+        }
+    };
+}
+
+catalog! {
+    "E0382" => "catalog/E0382.md",
+    "E0308" => "catalog/E0308.md",
+    "E0425" => "catalog/E0425.md",
+    "E0384" => "catalog/E0384.md",
+    "E0594" => "catalog/E0594.md",
+    "E0106" => "catalog/E0106.md",
+    "E0515" => "catalog/E0515.md",
+}
+
+fn catalog() -> &'static HashMap<&'static str, &'static str> {
+    static CATALOG: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    CATALOG.get_or_init(build_catalog)
+}
+
+/// Returns the markdown explanation for a rustc error code, e.g. `"E0382"`.
+pub fn explanation_for(code: &str) -> Option<&'static str> {
+    catalog().get(code).copied()
+}
+
+/// A diagnostic paired with its catalog explanation, if it has one.
+#[derive(Debug, Clone)]
+pub struct EnrichedDiagnostic {
+    pub diagnostic: RustcDiagnostic,
+    pub explanation: Option<&'static str>,
+}
+
+/// Attaches the matching catalog explanation to every diagnostic that
+/// carries an error code we have an entry for.
+pub fn enrich(diagnostics: Vec<RustcDiagnostic>) -> Vec<EnrichedDiagnostic> {
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let explanation = diagnostic
+                .code
+                .as_ref()
+                .and_then(|code| explanation_for(&code.code));
+            EnrichedDiagnostic {
+                diagnostic,
+                explanation,
+            }
+        })
+        .collect()
+}
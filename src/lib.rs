@@ -0,0 +1,12 @@
+//! Core library for the error-debugging MCP server.
+//!
+//! This crate detects and explains Rust compiler diagnostics and exposes the
+//! results as a set of [Model Context Protocol](https://modelcontextprotocol.io)
+//! tools that an agent can call while debugging a source file.
+
+pub mod autofix;
+pub mod diagnostics;
+pub mod explain;
+pub mod mcp;
+pub mod rustc_diagnostics;
+pub mod scanner;
@@ -0,0 +1,19 @@
+//! MCP tool plumbing: a small trait + registry (see [`registry`]) and the
+//! tool implementations themselves (see [`tools`]).
+
+mod registry;
+mod tools;
+
+pub use registry::{McpTool, ToolError, ToolRegistry};
+pub use tools::apply_fixes::ApplyFixesTool;
+pub use tools::detect_errors::DetectErrorsTool;
+pub use tools::explain_error::ExplainErrorTool;
+
+/// Builds the registry of tools this server exposes.
+pub fn default_registry() -> ToolRegistry {
+    let mut registry = ToolRegistry::new();
+    registry.register(Box::new(ApplyFixesTool));
+    registry.register(Box::new(ExplainErrorTool));
+    registry.register(Box::new(DetectErrorsTool));
+    registry
+}
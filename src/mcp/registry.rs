@@ -0,0 +1,61 @@
+//! A minimal MCP tool trait and registry.
+//!
+//! This deliberately doesn't depend on any particular transport (stdio, SSE,
+//! ...); the binary that wires the server up to a transport dispatches into
+//! a [`ToolRegistry`] by tool name.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use thiserror::Error;
+
+/// Errors a tool call can fail with.
+#[derive(Debug, Error)]
+pub enum ToolError {
+    #[error("invalid arguments for tool `{tool}`: {reason}")]
+    InvalidArguments { tool: &'static str, reason: String },
+    #[error("tool execution failed: {0}")]
+    Execution(#[from] anyhow::Error),
+}
+
+/// A single MCP tool: a name, a JSON schema for its arguments, and the call
+/// itself.
+pub trait McpTool: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn input_schema(&self) -> Value;
+    fn call(&self, args: Value) -> Result<Value, ToolError>;
+}
+
+/// The set of tools this server exposes, keyed by name.
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: HashMap<&'static str, Box<dyn McpTool>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, tool: Box<dyn McpTool>) {
+        self.tools.insert(tool.name(), tool);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn McpTool> {
+        self.tools.get(name).map(|tool| tool.as_ref())
+    }
+
+    pub fn list(&self) -> impl Iterator<Item = &dyn McpTool> {
+        self.tools.values().map(|tool| tool.as_ref())
+    }
+
+    pub fn dispatch(&self, name: &str, args: Value) -> Result<Value, ToolError> {
+        self.get(name)
+            .ok_or_else(|| ToolError::InvalidArguments {
+                tool: "<unknown>",
+                reason: format!("no tool named `{name}`"),
+            })?
+            .call(args)
+    }
+}
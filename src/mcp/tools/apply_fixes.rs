@@ -0,0 +1,56 @@
+//! The `apply_fixes` MCP tool: patches a file's machine-applicable rustc
+//! suggestions and hands back the result.
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::autofix;
+use crate::mcp::{McpTool, ToolError};
+
+#[derive(Debug, Deserialize)]
+struct ApplyFixesArgs {
+    file: PathBuf,
+}
+
+/// Applies every `MachineApplicable` rustc suggestion in a file and returns
+/// the patched source plus a unified diff; non-machine-applicable
+/// suggestions come back as advisory only.
+pub struct ApplyFixesTool;
+
+impl McpTool for ApplyFixesTool {
+    fn name(&self) -> &'static str {
+        "apply_fixes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Apply machine-applicable rustc suggestions to a file and return the patched source and a unified diff."
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file": {
+                    "type": "string",
+                    "description": "Path to the Rust source file to fix."
+                }
+            },
+            "required": ["file"]
+        })
+    }
+
+    fn call(&self, args: Value) -> Result<Value, ToolError> {
+        let args: ApplyFixesArgs =
+            serde_json::from_value(args).map_err(|err| ToolError::InvalidArguments {
+                tool: self.name(),
+                reason: err.to_string(),
+            })?;
+
+        let report = autofix::autofix_file(&args.file)
+            .map_err(|err| ToolError::Execution(anyhow::anyhow!(err)))?;
+
+        Ok(serde_json::to_value(report).expect("AutofixReport always serializes"))
+    }
+}
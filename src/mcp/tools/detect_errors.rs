@@ -0,0 +1,55 @@
+//! The `detect_errors` MCP tool: runs every detection pass over a file and
+//! returns the aggregated, phase-sorted diagnostic report.
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::diagnostics;
+use crate::mcp::{McpTool, ToolError};
+
+#[derive(Debug, Deserialize)]
+struct DetectErrorsArgs {
+    file: PathBuf,
+}
+
+/// Detects every independent error in a file — syntax, name resolution,
+/// type, and borrow-check problems alike — as one phase-sorted list.
+pub struct DetectErrorsTool;
+
+impl McpTool for DetectErrorsTool {
+    fn name(&self) -> &'static str {
+        "detect_errors"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detect every independent error in a Rust file, grouped by compiler phase."
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file": {
+                    "type": "string",
+                    "description": "Path to the Rust source file to analyze."
+                }
+            },
+            "required": ["file"]
+        })
+    }
+
+    fn call(&self, args: Value) -> Result<Value, ToolError> {
+        let args: DetectErrorsArgs =
+            serde_json::from_value(args).map_err(|err| ToolError::InvalidArguments {
+                tool: self.name(),
+                reason: err.to_string(),
+            })?;
+
+        let report = diagnostics::detect_file(&args.file)
+            .map_err(|err| ToolError::Execution(anyhow::anyhow!(err)))?;
+
+        Ok(serde_json::to_value(report).expect("DetectionReport always serializes"))
+    }
+}
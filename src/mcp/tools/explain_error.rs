@@ -0,0 +1,56 @@
+//! The `explain_error` MCP tool: looks up the catalog entry for a rustc
+//! error code.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::explain;
+use crate::mcp::{McpTool, ToolError};
+
+#[derive(Debug, Deserialize)]
+struct ExplainErrorArgs {
+    code: String,
+}
+
+/// Returns the markdown explanation for a rustc error code, e.g. `"E0382"`.
+pub struct ExplainErrorTool;
+
+impl McpTool for ExplainErrorTool {
+    fn name(&self) -> &'static str {
+        "explain_error"
+    }
+
+    fn description(&self) -> &'static str {
+        "Return the long-form explanation for a rustc error code, e.g. E0382."
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "A rustc error code, e.g. \"E0382\"."
+                }
+            },
+            "required": ["code"]
+        })
+    }
+
+    fn call(&self, args: Value) -> Result<Value, ToolError> {
+        let args: ExplainErrorArgs =
+            serde_json::from_value(args).map_err(|err| ToolError::InvalidArguments {
+                tool: self.name(),
+                reason: err.to_string(),
+            })?;
+
+        let explanation = explain::explanation_for(&args.code).ok_or_else(|| {
+            ToolError::InvalidArguments {
+                tool: self.name(),
+                reason: format!("no catalog entry for `{}`", args.code),
+            }
+        })?;
+
+        Ok(json!({ "code": args.code, "explanation": explanation }))
+    }
+}
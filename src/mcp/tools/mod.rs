@@ -0,0 +1,3 @@
+pub mod apply_fixes;
+pub mod detect_errors;
+pub mod explain_error;
@@ -0,0 +1,133 @@
+//! Types and helpers for working with rustc's `--error-format=json` output.
+//!
+//! rustc (and `cargo check --message-format=json`) emit one diagnostic per
+//! line as a JSON object when asked. We deserialize just the fields this
+//! crate needs; unknown fields are silently ignored by serde.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Deserialize;
+
+/// How confident rustc is that applying a suggestion verbatim is safe.
+///
+/// Mirrors `rustc_errors::Applicability`. Only `MachineApplicable` spans are
+/// safe to apply without a human in the loop; the rest are surfaced as
+/// advisory suggestions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum Applicability {
+    MachineApplicable,
+    MaybeIncorrect,
+    HasPlaceholders,
+    Unspecified,
+}
+
+/// A source span as reported by rustc, including any suggested replacement.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RustcSpan {
+    pub file_name: String,
+    pub byte_start: u32,
+    pub byte_end: u32,
+    pub line_start: u32,
+    pub line_end: u32,
+    pub column_start: u32,
+    pub column_end: u32,
+    pub is_primary: bool,
+    pub label: Option<String>,
+    pub suggested_replacement: Option<String>,
+    pub suggestion_applicability: Option<Applicability>,
+}
+
+/// The rustc error code attached to a diagnostic, e.g. `E0382`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RustcErrorCode {
+    pub code: String,
+}
+
+/// One diagnostic as emitted by `rustc --error-format=json`.
+///
+/// `children` holds the nested notes/help/suggestions rustc attaches to the
+/// primary message, e.g. "help: consider making this binding mutable".
+#[derive(Debug, Clone, Deserialize)]
+pub struct RustcDiagnostic {
+    pub message: String,
+    pub code: Option<RustcErrorCode>,
+    pub level: String,
+    #[serde(default)]
+    pub spans: Vec<RustcSpan>,
+    #[serde(default)]
+    pub children: Vec<RustcDiagnostic>,
+    pub rendered: Option<String>,
+}
+
+/// Builds a path under the system temp directory that's unique to this call,
+/// so concurrent invocations (different files, different MCP clients) never
+/// race on the same output or scratch file.
+fn unique_temp_path(label: &str, extension: &str) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let sequence = NEXT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "error-debugging-mcp-{label}-{}-{sequence}.{extension}",
+        std::process::id()
+    ))
+}
+
+/// Runs `rustc --error-format=json` against `path` and returns every
+/// diagnostic it printed, parsed from the newline-delimited JSON on stderr.
+///
+/// Lines that aren't valid diagnostic JSON (rustc prints a handful of banner
+/// lines before bailing on some inputs) are skipped rather than erroring.
+/// Compiles as a library so standalone snippets don't need a `fn main`.
+fn run_rustc_json(path: &Path) -> io::Result<Vec<RustcDiagnostic>> {
+    let out_path = unique_temp_path("out", "rmeta");
+    let output = Command::new("rustc")
+        .arg("--error-format=json")
+        .arg("--crate-type=lib")
+        .arg("-o")
+        .arg(&out_path)
+        .arg(path)
+        .output()?;
+    let _ = fs::remove_file(&out_path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RustcDiagnostic>(line).ok())
+        .collect())
+}
+
+/// Checks a snippet of source text directly, without requiring the caller to
+/// have it on disk under a stable name: writes it to a private, uniquely
+/// named temp file for the duration of the call.
+///
+/// Diagnostics come back with byte offsets relative to the start of
+/// `source`, exactly as if `source` itself were the file on disk.
+pub fn check_source(source: &str) -> io::Result<Vec<RustcDiagnostic>> {
+    let source_path = unique_temp_path("src", "rs");
+    fs::write(&source_path, source)?;
+    let result = run_rustc_json(&source_path);
+    let _ = fs::remove_file(&source_path);
+    result
+}
+
+/// The primary span of a diagnostic, if it has one.
+pub fn primary_span(diagnostic: &RustcDiagnostic) -> Option<&RustcSpan> {
+    diagnostic.spans.iter().find(|span| span.is_primary)
+}
+
+/// Whether rustc got far enough to build a complete AST for the whole input,
+/// as opposed to aborting on a fatal parse error partway through.
+///
+/// A fatal parse error produces only codeless diagnostics (the error itself,
+/// plus rustc's "aborting due to N previous error(s)" and "try --explain"
+/// meta-lines); once rustc reaches name resolution or type checking, every
+/// diagnostic from there on carries a `code` (an E-number, or a lint code
+/// like `dead_code`). So an empty diagnostic list, or any diagnostic with a
+/// code, means parsing succeeded and it's safe to trust whole-file results.
+pub fn parsed_completely(diagnostics: &[RustcDiagnostic]) -> bool {
+    diagnostics.is_empty() || diagnostics.iter().any(|diagnostic| diagnostic.code.is_some())
+}
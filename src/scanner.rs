@@ -0,0 +1,551 @@
+//! Recovery-based syntax scanner.
+//!
+//! `rustc` bails out after the first fatal parse error, so a file with
+//! several independent syntax problems (unclosed delimiters, a statement
+//! where a block was expected, ...) only ever reports the first one. This
+//! module re-scans the raw source text itself, tracking delimiter nesting
+//! and a couple of common malformed patterns, so the server can surface
+//! every one of them instead of just whichever rustc gave up on first.
+//!
+//! This is intentionally not a real parser: it tokenizes just enough to
+//! track brackets and recognize a handful of shapes, and keeps going past
+//! anything it doesn't understand rather than failing closed.
+
+/// One problem found by the scanner, independent of anything rustc reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanDiagnostic {
+    pub message: String,
+    pub byte_offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    OpenParen,
+    CloseParen,
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Semi,
+    Pipe,
+    Colon,
+    Comma,
+    Lt,
+    Gt,
+    Ident,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    byte_offset: usize,
+    line: usize,
+    column: usize,
+}
+
+/// Tokenizes just enough of `source` to track delimiters and a few
+/// recognizable keywords, skipping over string/char literals and comments
+/// so punctuation inside them is never mistaken for real structure.
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    let mut line = 1usize;
+    let mut column = 1usize;
+
+    macro_rules! advance {
+        ($ch:expr) => {{
+            if $ch == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }};
+    }
+
+    while let Some(&(byte_offset, ch)) = chars.peek() {
+        match ch {
+            '/' => {
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                match lookahead.peek().map(|&(_, c)| c) {
+                    Some('/') => {
+                        while let Some(&(_, c)) = chars.peek() {
+                            if c == '\n' {
+                                break;
+                            }
+                            advance!(c);
+                            chars.next();
+                        }
+                    }
+                    Some('*') => {
+                        chars.next();
+                        advance!('/');
+                        chars.next();
+                        advance!('*');
+                        let mut depth = 1usize;
+                        while depth > 0 {
+                            match chars.next() {
+                                Some((_, '*')) if matches!(chars.peek(), Some(&(_, '/'))) => {
+                                    advance!('*');
+                                    chars.next();
+                                    advance!('/');
+                                    depth -= 1;
+                                }
+                                Some((_, '/')) if matches!(chars.peek(), Some(&(_, '*'))) => {
+                                    advance!('/');
+                                    chars.next();
+                                    advance!('*');
+                                    depth += 1;
+                                }
+                                Some((_, c)) => advance!(c),
+                                None => break,
+                            }
+                        }
+                    }
+                    _ => {
+                        advance!(ch);
+                        chars.next();
+                    }
+                }
+            }
+            '"' => {
+                advance!(ch);
+                chars.next();
+                while let Some(&(_, c)) = chars.peek() {
+                    advance!(c);
+                    chars.next();
+                    if c == '\\' {
+                        if let Some(&(_, escaped)) = chars.peek() {
+                            advance!(escaped);
+                            chars.next();
+                        }
+                        continue;
+                    }
+                    if c == '"' {
+                        break;
+                    }
+                }
+            }
+            '\'' => {
+                // Could be a char literal or a lifetime; either way, string
+                // contents never hide delimiters we care about, so a best
+                // effort skip of `'x'` / `'\x'` is enough.
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                let second = lookahead.next();
+                let third = lookahead.next();
+                let is_char_literal = matches!(
+                    (second, third),
+                    (Some((_, c)), Some((_, '\''))) if c != '\\'
+                ) || matches!(second, Some((_, '\\')));
+                advance!(ch);
+                chars.next();
+                if is_char_literal {
+                    while let Some(&(_, c)) = chars.peek() {
+                        advance!(c);
+                        chars.next();
+                        if c == '\'' {
+                            break;
+                        }
+                    }
+                }
+            }
+            // `->` isn't a delimiter: consume both characters without
+            // emitting a token so its `>` is never mistaken for a generic's
+            // closing angle bracket by callers that track `Lt`/`Gt` depth.
+            '-' if matches!(chars.clone().nth(1), Some((_, '>'))) => {
+                advance!(ch);
+                chars.next();
+                let (_, next) = chars.next().expect("peeked Some('>') above");
+                advance!(next);
+            }
+            '(' | '{' | '[' | ')' | '}' | ']' | ';' | '|' | ':' | ',' | '<' | '>' => {
+                let kind = match ch {
+                    '(' => TokenKind::OpenParen,
+                    ')' => TokenKind::CloseParen,
+                    '{' => TokenKind::OpenBrace,
+                    '}' => TokenKind::CloseBrace,
+                    '[' => TokenKind::OpenBracket,
+                    ']' => TokenKind::CloseBracket,
+                    ';' => TokenKind::Semi,
+                    '|' => TokenKind::Pipe,
+                    ':' => TokenKind::Colon,
+                    ',' => TokenKind::Comma,
+                    '<' => TokenKind::Lt,
+                    '>' => TokenKind::Gt,
+                    _ => unreachable!(),
+                };
+                tokens.push(Token {
+                    kind,
+                    text: ch.to_string(),
+                    byte_offset,
+                    line,
+                    column,
+                });
+                advance!(ch);
+                chars.next();
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = byte_offset;
+                let start_line = line;
+                let start_col = column;
+                let mut text = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        text.push(c);
+                        advance!(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Ident,
+                    text,
+                    byte_offset: start,
+                    line: start_line,
+                    column: start_col,
+                });
+            }
+            c => {
+                advance!(c);
+                chars.next();
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Reports every delimiter still open at end of file as "unclosed `x`
+/// opened here", instead of the single fatal error rustc would stop at.
+fn scan_unclosed_delimiters(tokens: &[Token]) -> Vec<ScanDiagnostic> {
+    let mut stack: Vec<&Token> = Vec::new();
+    let mut skipped: Vec<&Token> = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::OpenParen | TokenKind::OpenBrace | TokenKind::OpenBracket => {
+                stack.push(token);
+            }
+            TokenKind::CloseParen | TokenKind::CloseBrace | TokenKind::CloseBracket => {
+                let opener = match token.kind {
+                    TokenKind::CloseParen => TokenKind::OpenParen,
+                    TokenKind::CloseBrace => TokenKind::OpenBrace,
+                    _ => TokenKind::OpenBracket,
+                };
+                // A closer matches the nearest opener of its own kind; any
+                // opener of another kind above that one is never closed.
+                if let Some(position) = stack.iter().rposition(|open| open.kind == opener) {
+                    skipped.extend(stack.drain(position + 1..));
+                    stack.truncate(position);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for unclosed in skipped.into_iter().chain(stack) {
+        diagnostics.push(ScanDiagnostic {
+            message: format!("unclosed `{}` opened here", unclosed.text),
+            byte_offset: unclosed.byte_offset,
+            line: unclosed.line,
+            column: unclosed.column,
+            suggestion: Some(format!("add a matching closing delimiter for this `{}`", unclosed.text)),
+        });
+    }
+
+    diagnostics
+}
+
+/// Finds `if <cond>` directly followed by a statement token instead of a
+/// block, e.g. the classic `if cond` / next-line-statement typo.
+fn scan_missing_blocks(tokens: &[Token]) -> Vec<ScanDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (index, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Ident || token.text != "if" {
+            continue;
+        }
+        // Walk forward past the condition expression: everything up to the
+        // first top-level `{` or `;`.
+        let mut depth = 0i32;
+        let mut saw_block = false;
+        let mut stopped_at = None;
+        for candidate in &tokens[index + 1..] {
+            match candidate.kind {
+                TokenKind::OpenParen | TokenKind::OpenBracket => depth += 1,
+                TokenKind::CloseParen | TokenKind::CloseBracket => depth -= 1,
+                TokenKind::OpenBrace if depth == 0 => {
+                    saw_block = true;
+                    break;
+                }
+                TokenKind::Semi if depth == 0 => {
+                    stopped_at = Some(candidate);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        if !saw_block {
+            if let Some(stray_semi) = stopped_at {
+                diagnostics.push(ScanDiagnostic {
+                    message: "expected `{` after `if` condition, found a bare statement".into(),
+                    byte_offset: stray_semi.byte_offset,
+                    line: stray_semi.line,
+                    column: stray_semi.column,
+                    suggestion: Some("place this code inside a block: `if cond { ... }`".into()),
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Finds `|args| expr;` used directly as a call argument, where the
+/// trailing `;` turns what looked like a closure body into a statement the
+/// parser can't use as an expression.
+fn scan_closure_body_semicolons(tokens: &[Token]) -> Vec<ScanDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut call_depth = 0i32;
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::OpenParen => call_depth += 1,
+            TokenKind::CloseParen => call_depth -= 1,
+            TokenKind::Pipe if call_depth > 0 => {
+                // Find the matching closing `|` of the parameter list.
+                let Some(close_pipe_rel) = tokens[index + 1..]
+                    .iter()
+                    .position(|candidate| candidate.kind == TokenKind::Pipe)
+                else {
+                    continue;
+                };
+                let body_start = index + 1 + close_pipe_rel + 1;
+                let mut depth = 0i32;
+                for candidate in &tokens[body_start..] {
+                    match candidate.kind {
+                        TokenKind::OpenParen | TokenKind::OpenBracket | TokenKind::OpenBrace => {
+                            depth += 1
+                        }
+                        TokenKind::CloseParen | TokenKind::CloseBracket | TokenKind::CloseBrace => {
+                            if depth == 0 {
+                                break;
+                            }
+                            depth -= 1;
+                        }
+                        TokenKind::Semi if depth == 0 => {
+                            diagnostics.push(ScanDiagnostic {
+                                message: "closure body followed by `;` inside a call argument list".into(),
+                                byte_offset: candidate.byte_offset,
+                                line: candidate.line,
+                                column: candidate.column,
+                                suggestion: Some("wrap the closure body in braces: `|args| { expr; }`".into()),
+                            });
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    diagnostics
+}
+
+/// Finds a function parameter list where a parameter name is never followed
+/// by `: Type` before its terminating `,` or `)`, e.g. `fn f(a: i32, b)`.
+/// rustc's parser treats a bare identifier there as the start of a pattern
+/// with no type annotation and bails immediately, so this is one of the
+/// fatal-parse-error shapes the recovery scanner needs to catch itself.
+fn scan_missing_parameter_types(tokens: &[Token]) -> Vec<ScanDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (index, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Ident || token.text != "fn" {
+            continue;
+        }
+        // A real function item's `fn` is followed by its name; a bare `fn(`
+        // with nothing in between is a function-pointer *type* (e.g. a
+        // parameter typed `cb: fn(i32) -> i32`), which has no parameter
+        // list of its own to check here.
+        let Some(name) = tokens.get(index + 1) else {
+            continue;
+        };
+        if name.kind != TokenKind::Ident {
+            continue;
+        }
+        // Skip the function name to the parameter list's opening `(`,
+        // tracking angle-bracket depth along the way: generic type
+        // parameters (`fn f<T>(...)`) sit in between, and a trait bound
+        // like `fn f<T: Fn(i32) -> i32>(...)` can itself contain a `(` that
+        // isn't the real parameter list's.
+        let mut angle_depth = 0i32;
+        let open_paren = tokens[index + 2..].iter().enumerate().find_map(
+            |(offset, candidate)| match candidate.kind {
+                TokenKind::Lt => {
+                    angle_depth += 1;
+                    None
+                }
+                TokenKind::Gt => {
+                    angle_depth -= 1;
+                    None
+                }
+                TokenKind::OpenParen if angle_depth == 0 => Some(index + 2 + offset),
+                _ => None,
+            },
+        );
+        let Some(open_paren) = open_paren else {
+            continue;
+        };
+
+        // Walk the parameter list one parameter at a time: each parameter is
+        // an identifier (its name) that must be followed by `:` before the
+        // next top-level `,` or the list's closing `)`.
+        let mut cursor = open_paren + 1;
+        let mut depth = 0i32;
+        while cursor < tokens.len() {
+            let candidate = &tokens[cursor];
+            match candidate.kind {
+                TokenKind::CloseParen if depth == 0 => break,
+                TokenKind::OpenParen | TokenKind::OpenBracket | TokenKind::OpenBrace | TokenKind::Lt => {
+                    depth += 1;
+                    cursor += 1;
+                }
+                TokenKind::CloseParen
+                | TokenKind::CloseBracket
+                | TokenKind::CloseBrace
+                | TokenKind::Gt => {
+                    depth -= 1;
+                    cursor += 1;
+                }
+                // `mut` is a binding modifier, not the parameter's name
+                // (`fn f(mut x: i32)`); skip it so the real name is what
+                // gets checked for a following `:`.
+                TokenKind::Ident if depth == 0 && candidate.text == "mut" => {
+                    cursor += 1;
+                }
+                TokenKind::Ident if depth == 0 => {
+                    let name = candidate;
+                    let next = tokens.get(cursor + 1);
+                    let has_type = next.is_some_and(|next| next.kind == TokenKind::Colon);
+                    // `self`/`&self`/`&mut self` never carries a type.
+                    if !has_type && name.text != "self" {
+                        diagnostics.push(ScanDiagnostic {
+                            message: format!(
+                                "parameter `{}` is missing a type annotation",
+                                name.text
+                            ),
+                            byte_offset: name.byte_offset,
+                            line: name.line,
+                            column: name.column,
+                            suggestion: Some(format!("add a type: `{}: T`", name.text)),
+                        });
+                    }
+                    // Skip to the parameter's terminating `,`/`)` so a type
+                    // like `Vec<i32>` isn't itself mistaken for a parameter.
+                    cursor += 1;
+                    while let Some(skip) = tokens.get(cursor) {
+                        match skip.kind {
+                            TokenKind::CloseParen if depth == 0 => break,
+                            TokenKind::Comma if depth == 0 => {
+                                cursor += 1;
+                                break;
+                            }
+                            TokenKind::OpenParen
+                            | TokenKind::OpenBracket
+                            | TokenKind::OpenBrace
+                            | TokenKind::Lt => {
+                                depth += 1;
+                                cursor += 1;
+                            }
+                            TokenKind::CloseParen
+                            | TokenKind::CloseBracket
+                            | TokenKind::CloseBrace
+                            | TokenKind::Gt => {
+                                depth -= 1;
+                                cursor += 1;
+                            }
+                            _ => cursor += 1,
+                        }
+                    }
+                }
+                _ => cursor += 1,
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Splits `source` into top-level chunks: byte ranges that each return to
+/// zero delimiter depth by their end, so they can be compiled independently.
+///
+/// rustc parses a whole file as one translation unit, so a single fatal
+/// parse error anywhere (an unclosed brace, say) blocks semantic analysis of
+/// everything else in the file, even code with no relation to the broken
+/// item. Feeding rustc one brace-balanced top-level item at a time means an
+/// early syntax error in one item can no longer swallow the real borrow/
+/// type/resolution diagnostics that the other, well-formed items would
+/// otherwise produce on their own. An item whose delimiters never rebalance
+/// (the broken file really is missing a `}`) simply becomes one large chunk
+/// running to EOF, same as rustc would see it.
+pub fn split_top_level_chunks(source: &str) -> Vec<(usize, usize)> {
+    let tokens = tokenize(source);
+    let mut chunks = Vec::new();
+    let mut depth = 0i32;
+    let mut chunk_start = 0usize;
+
+    for token in &tokens {
+        match token.kind {
+            TokenKind::OpenParen | TokenKind::OpenBrace | TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => depth -= 1,
+            // Only a top-level `}` or `;` actually completes an item (`fn`,
+            // `struct`, `impl`, ... end in `}`; `use`/`const`/`type` end in
+            // `;`). A parameter list's `()` or an array type's `[]` can also
+            // return to depth zero mid-item, but that's not an item
+            // boundary, just a balanced sub-expression inside one.
+            TokenKind::CloseBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    let end = token.byte_offset + token.text.len();
+                    chunks.push((chunk_start, end));
+                    chunk_start = end;
+                }
+            }
+            TokenKind::Semi if depth == 0 => {
+                let end = token.byte_offset + token.text.len();
+                chunks.push((chunk_start, end));
+                chunk_start = end;
+            }
+            _ => {}
+        }
+    }
+    if chunk_start < source.len() {
+        chunks.push((chunk_start, source.len()));
+    }
+
+    chunks
+}
+
+/// Runs every recovery pass over `source` and returns every diagnostic
+/// found, in source order.
+pub fn scan(source: &str) -> Vec<ScanDiagnostic> {
+    let tokens = tokenize(source);
+    let mut diagnostics = Vec::new();
+    diagnostics.extend(scan_unclosed_delimiters(&tokens));
+    diagnostics.extend(scan_missing_blocks(&tokens));
+    diagnostics.extend(scan_closure_body_semicolons(&tokens));
+    diagnostics.extend(scan_missing_parameter_types(&tokens));
+    diagnostics.sort_by_key(|diagnostic| diagnostic.byte_offset);
+    diagnostics
+}